@@ -0,0 +1,47 @@
+use crate::*;
+
+/// 认证加密（AEAD）算法：在加密的同时对密文及附加认证数据（AAD）生成认证标签，
+/// 使篡改可以在解密前被发现，而不是像普通的CipherAlgorithmTrait那样只负责加解密
+pub trait AeadTrait {
+    /// 该算法要求的Nonce长度
+    const NONCE_LENGTH: usize;
+    /// 该算法产生的认证标签长度
+    const TAG_LENGTH: usize;
+    /// 该算法产生的认证标签类型，用`ByteArray<TAG_LENGTH>`将标签长度固定在类型里，
+    /// 而不是仅靠`TAG_LENGTH`这个运行时常量自律
+    type Tag: AsRef<[u8]>;
+
+    /// 加密plaintext并认证aad，返回"密文 || 认证标签"
+    fn encrypt(&mut self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<ByteVector>;
+
+    /// 校验ciphertext末尾携带的认证标签，校验通过后返回解密出的明文
+    ///
+    /// 校验使用常数时间比较，校验失败时返回ChecksumValidationFailed，
+    /// 且不会在标签比较阶段提前释放任何明文信息
+    fn decrypt(&mut self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<ByteVector>;
+}
+
+/// 按照RFC 8439的方式，对aad和ciphertext构造Poly1305的输入：
+/// aad按16字节边界补0，ciphertext按16字节边界补0，
+/// 最后附上8字节小端aad长度和8字节小端ciphertext长度
+pub(crate) fn poly1305_auth_blocks(aad: &[u8], ciphertext: &[u8]) -> ByteVector {
+    let mut buf = ByteVector::new();
+
+    buf.extend_from_slice(aad);
+    pad_to_16(&mut buf);
+
+    buf.extend_from_slice(ciphertext);
+    pad_to_16(&mut buf);
+
+    buf.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+    buf.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+    buf
+}
+
+fn pad_to_16(buf: &mut ByteVector) {
+    let rem = buf.len() % 16;
+    if rem != 0 {
+        buf.extend(std::iter::repeat_n(0u8, 16 - rem));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::*;
+
+/// 底层分组密码原语：只负责对单个分组做加解密，不关心工作模式
+pub trait BlockCipherTrait {
+    /// 分组长度
+    const BLOCK_LENGTH: usize;
+
+    /// 加密一个分组
+    fn encrypt_block(&self, block: &[u8]) -> Result<ByteVector>;
+    /// 解密一个分组
+    fn decrypt_block(&self, block: &[u8]) -> Result<ByteVector>;
+}
+
+/// 分组密码工作模式：基于某个BlockCipherTrait原语对单个分组进行加解密，
+/// 并就地推进iv（对CBC是上一个密文分组，对CTR是计数器），
+/// 使得对同一个mode实例的连续调用可以正确衔接
+pub trait BlockMode<C: BlockCipherTrait> {
+    /// 加密一个分组，iv在调用后被更新为下一次调用应使用的状态
+    fn encrypt_block(&self, cipher: &C, iv: &mut [u8], dst: &mut [u8], src: &[u8]) -> Result<()>;
+    /// 解密一个分组，iv在调用后被更新为下一次调用应使用的状态
+    fn decrypt_block(&self, cipher: &C, iv: &mut [u8], dst: &mut [u8], src: &[u8]) -> Result<()>;
+}
+
+/// CBC（密码分组链接）模式
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cbc;
+
+impl<C: BlockCipherTrait> BlockMode<C> for Cbc {
+    fn encrypt_block(&self, cipher: &C, iv: &mut [u8], dst: &mut [u8], src: &[u8]) -> Result<()> {
+        let mixed = xor(src, iv)?;
+        let encrypted = cipher.encrypt_block(&mixed)?;
+        memcpy(dst, &encrypted)?;
+        memcpy(iv, &encrypted)?;
+        Ok(())
+    }
+
+    fn decrypt_block(&self, cipher: &C, iv: &mut [u8], dst: &mut [u8], src: &[u8]) -> Result<()> {
+        let decrypted = cipher.decrypt_block(src)?;
+        let plain = xor(&decrypted, iv)?;
+        memcpy(dst, &plain)?;
+        memcpy(iv, src)?;
+        Ok(())
+    }
+}
+
+/// CTR（计数器）模式：把iv当作大端计数器分组，用密码原语加密计数器得到密钥流，
+/// 与数据异或即可，加解密是同一段代码，每个分组后计数器加一并在溢出时回绕
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ctr;
+
+impl Ctr {
+    fn apply_keystream(&self, cipher: &impl BlockCipherTrait, iv: &mut [u8], dst: &mut [u8], src: &[u8]) -> Result<()> {
+        let keystream = cipher.encrypt_block(iv)?;
+        let out = xor(src, &keystream)?;
+        memcpy(dst, &out)?;
+        increment_counter(iv);
+        Ok(())
+    }
+}
+
+impl<C: BlockCipherTrait> BlockMode<C> for Ctr {
+    fn encrypt_block(&self, cipher: &C, iv: &mut [u8], dst: &mut [u8], src: &[u8]) -> Result<()> {
+        self.apply_keystream(cipher, iv, dst, src)
+    }
+
+    fn decrypt_block(&self, cipher: &C, iv: &mut [u8], dst: &mut [u8], src: &[u8]) -> Result<()> {
+        self.apply_keystream(cipher, iv, dst, src)
+    }
+}
+
+/// 将counter当作大端整数加一，支持溢出回绕
+fn increment_counter(counter: &mut [u8]) {
+    for byte in counter.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increment_counter_wraps_around() {
+        let mut counter = [0xFFu8, 0xFF, 0xFF];
+        increment_counter(&mut counter);
+        assert_eq!(counter, [0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_increment_counter_carries() {
+        let mut counter = [0x00u8, 0xFF];
+        increment_counter(&mut counter);
+        assert_eq!(counter, [0x01, 0x00]);
+    }
+}
@@ -1,8 +1,13 @@
-use ::chacha20::cipher::{KeyIvInit, StreamCipher};
+use ::chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
 use ::chacha20::ChaCha20 as ExChaCha20;
+use ::poly1305::{
+    universal_hash::{KeyInit, UniversalHash},
+    Key as Poly1305Key, Poly1305,
+};
 
+use crate::crypter::aead::poly1305_auth_blocks;
 use crate::crypter::{
-    CipherAlgorithmBaseTrait, CipherAlgorithmTrait, CipherAlgorithmType, IVKeyNewTrait,
+    AeadTrait, CipherAlgorithmBaseTrait, CipherAlgorithmTrait, CipherAlgorithmType, IVKeyNewTrait,
 };
 
 use crate::*;
@@ -11,6 +16,10 @@ use crate::*;
 pub const CHACHA20_IV_LENGTH: usize = 12;
 /// ChaCha20的Key长度
 pub const CHACHA20_KEY_LENGTH: usize = 32;
+/// ChaCha20-Poly1305认证标签长度
+pub const CHACHA20_POLY1305_TAG_LENGTH: usize = 16;
+/// 一个ChaCha20分组的长度，Poly1305的一次性密钥取自该分组的前半部分
+const CHACHA20_BLOCK_LENGTH: u32 = 64;
 
 /// ChaCha20密码算法
 pub struct ChaCha20CipherAlgorithm {
@@ -21,10 +30,12 @@ impl CipherAlgorithmBaseTrait for ChaCha20CipherAlgorithm {
     const IV_LENGTH: usize = CHACHA20_IV_LENGTH;
     const KEY_LENGTH: usize = CHACHA20_KEY_LENGTH;
     const CIPHER_ALGORITHM_TYPE: CipherAlgorithmType = CipherAlgorithmType::Stream;
+    const ALGORITHM_ID: u8 = 2;
 }
 
-impl CipherAlgorithmTrait for ChaCha20CipherAlgorithm {
-    fn crypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()> {
+impl ChaCha20CipherAlgorithm {
+    /// ChaCha20是流密码，加解密都是与密钥流异或，完全对称
+    fn apply_keystream(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()> {
         // 这里为了减少拷贝次数，先将src复制到dst中
         memcpy(dst_data, &src_data)?;
         self.m_algo.apply_keystream(dst_data);
@@ -32,6 +43,16 @@ impl CipherAlgorithmTrait for ChaCha20CipherAlgorithm {
     }
 }
 
+impl CipherAlgorithmTrait for ChaCha20CipherAlgorithm {
+    fn encrypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()> {
+        self.apply_keystream(src_data, dst_data)
+    }
+
+    fn decrypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()> {
+        self.apply_keystream(src_data, dst_data)
+    }
+}
+
 impl IVKeyNewTrait for ChaCha20CipherAlgorithm {
     fn new(iv: &[u8], key: &[u8]) -> Result<Self>
     where
@@ -48,14 +69,127 @@ impl IVKeyNewTrait for ChaCha20CipherAlgorithm {
     }
 }
 
+/// ChaCha20-Poly1305认证加密算法
+///
+/// 密钥固定，Nonce逐次调用传入：先用Nonce和计数器0生成的密钥流块推导出
+/// 一次性的Poly1305密钥，再用计数器1开始的密钥流加解密payload，
+/// 最后对AAD和密文计算Poly1305标签并附加在密文之后
+pub struct ChaCha20Poly1305CipherAlgorithm {
+    m_key: [u8; CHACHA20_KEY_LENGTH],
+}
+
+impl ChaCha20Poly1305CipherAlgorithm {
+    /// 使用固定长度的Key构造算法实例
+    pub fn new(key: &[u8]) -> Result<Self> {
+        if key.len() != CHACHA20_KEY_LENGTH {
+            return Err(CrypterError::InvalidKeyLength.into());
+        }
+        let mut m_key = [0u8; CHACHA20_KEY_LENGTH];
+        memcpy(&mut m_key, key)?;
+        Ok(ChaCha20Poly1305CipherAlgorithm { m_key })
+    }
+
+    /// 用计数器0的密钥流块推导一次性Poly1305密钥
+    fn derive_poly1305_key(&self, nonce: &[u8]) -> Poly1305Key {
+        let mut cipher = ExChaCha20::new(self.m_key.as_ref().into(), nonce.into());
+        let mut block = [0u8; 32];
+        cipher.apply_keystream(&mut block);
+        *Poly1305Key::from_slice(&block)
+    }
+
+    /// 计数器从1开始加密/解密payload，复用同一段代码即可双向使用
+    fn apply_payload_keystream(&self, nonce: &[u8], data: &mut [u8]) {
+        let mut cipher = ExChaCha20::new(self.m_key.as_ref().into(), nonce.into());
+        cipher.seek(CHACHA20_BLOCK_LENGTH);
+        cipher.apply_keystream(data);
+    }
+
+    fn compute_tag(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> ByteArray<CHACHA20_POLY1305_TAG_LENGTH> {
+        let poly_key = self.derive_poly1305_key(nonce);
+        let mut mac = Poly1305::new(&poly_key);
+        mac.update_padded(&poly1305_auth_blocks(aad, ciphertext));
+        let block: [u8; CHACHA20_POLY1305_TAG_LENGTH] = mac.finalize().into();
+        block.into()
+    }
+}
+
+impl AeadTrait for ChaCha20Poly1305CipherAlgorithm {
+    const NONCE_LENGTH: usize = CHACHA20_IV_LENGTH;
+    const TAG_LENGTH: usize = CHACHA20_POLY1305_TAG_LENGTH;
+    type Tag = ByteArray<CHACHA20_POLY1305_TAG_LENGTH>;
+
+    fn encrypt(&mut self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<ByteVector> {
+        if nonce.len() != Self::NONCE_LENGTH {
+            return Err(CrypterError::InvalidIVLength.into());
+        }
+
+        let mut ciphertext = plaintext.to_vec();
+        self.apply_payload_keystream(nonce, &mut ciphertext);
+
+        let tag = self.compute_tag(nonce, aad, &ciphertext);
+        ciphertext.extend_from_slice(tag.as_ref());
+        Ok(ciphertext)
+    }
+
+    fn decrypt(&mut self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<ByteVector> {
+        if nonce.len() != Self::NONCE_LENGTH {
+            return Err(CrypterError::InvalidIVLength.into());
+        }
+        if ciphertext.len() < Self::TAG_LENGTH {
+            return Err(CrypterError::ChecksumValidationFailed.into());
+        }
+
+        let (body, stored_tag) = ciphertext.split_at(ciphertext.len() - Self::TAG_LENGTH);
+        let expected_tag = self.compute_tag(nonce, aad, body);
+
+        if !ct_eq(expected_tag.as_ref(), stored_tag) {
+            return Err(CrypterError::ChecksumValidationFailed.into());
+        }
+
+        let mut plaintext = body.to_vec();
+        self.apply_payload_keystream(nonce, &mut plaintext);
+        Ok(plaintext)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crypter::{StringCrypter, StringCrypterTrait};
 
+    #[test]
+    fn test_chacha20_poly1305_roundtrip() {
+        let key = [0x11u8; CHACHA20_KEY_LENGTH];
+        let nonce = [0x22u8; CHACHA20_IV_LENGTH];
+        let aad = b"header";
+        let plaintext = b"hello, aead world!";
+
+        let mut algo = ChaCha20Poly1305CipherAlgorithm::new(&key).unwrap();
+        let ciphertext = algo.encrypt(&nonce, aad, plaintext).unwrap();
+        let decrypted = algo.decrypt(&nonce, aad, &ciphertext).unwrap();
+        assert_eq!(decrypted, plaintext.to_vec());
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_detects_tampering() {
+        let key = [0x11u8; CHACHA20_KEY_LENGTH];
+        let nonce = [0x22u8; CHACHA20_IV_LENGTH];
+        let aad = b"header";
+        let plaintext = b"hello, aead world!";
+
+        let mut algo = ChaCha20Poly1305CipherAlgorithm::new(&key).unwrap();
+        let mut ciphertext = algo.encrypt(&nonce, aad, plaintext).unwrap();
+        let last = ciphertext.len() - 1;
+        ciphertext[last] ^= 0x01;
+
+        let err = algo.decrypt(&nonce, aad, &ciphertext).unwrap_err();
+        assert!(matches!(err, Error::Crypter(CrypterError::ChecksumValidationFailed)));
+    }
+
     #[test]
     fn test_chacha20() {
-        let string_crypter = StringCrypter::<ChaCha20CipherAlgorithm>::default();
+        // 测试里用很小的迭代次数，避免每次跑测试都要等真实的PBKDF2开销
+        let string_crypter = StringCrypter::<ChaCha20CipherAlgorithm>::with_iterations(4);
         let ciphertext = string_crypter.encrypt("123456", "qwerty").unwrap();
         println!("ciphertext: {ciphertext}");
         let plaintext = string_crypter.decrypt(&ciphertext, "qwerty").unwrap();
@@ -0,0 +1,150 @@
+use crate::crypter::kdf::SALT_LENGTH;
+use crate::*;
+
+/// 当前支持解析的密文容器版本
+///
+/// v1是最初不带KDF盐/迭代次数的布局，v2在IV之后插入了salt和iterations字段，
+/// 属于不兼容的布局变更，因此版本号必须前进，否则v1的旧容器会被错误地按v2解析
+pub(crate) const CONTAINER_VERSION: u8 = 2;
+
+/// 算法标识字节的最高位用来标记原始长度字段是1字节还是8字节，
+/// 短消息可以省下7字节的容器开销
+const LENGTH_IS_WIDE_FLAG: u8 = 0x80;
+/// 达到或超过该长度的原始明文需要用8字节长度字段表示
+const WIDE_LENGTH_THRESHOLD: u64 = 0x100;
+
+/// 解析出的密文容器内容
+pub(crate) struct DecodedContainer {
+    /// 加密该消息所使用的算法标识
+    pub algorithm_id: u8,
+    /// 加密时使用的IV
+    pub iv: ByteVector,
+    /// 派生密钥时使用的盐
+    pub salt: ByteVector,
+    /// 派生密钥时使用的迭代次数
+    pub iterations: u32,
+    /// 原始明文长度，用于在分组密码解密后去掉补齐的0字节
+    pub original_length: u64,
+    /// 密文载荷
+    pub payload: ByteVector,
+}
+
+/// 构造自描述的密文容器：版本号、算法标识（附带长度字段宽度标记）、IV、
+/// KDF盐、KDF迭代次数、原始明文长度、密文载荷，依次拼接
+pub(crate) fn encode_container(
+    algorithm_id: u8,
+    iv: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    original_length: u64,
+    payload: &[u8],
+) -> ByteVector {
+    let wide = original_length >= WIDE_LENGTH_THRESHOLD;
+
+    let mut container = ByteVector::new();
+    container.push(CONTAINER_VERSION);
+    container.push(if wide {
+        algorithm_id | LENGTH_IS_WIDE_FLAG
+    } else {
+        algorithm_id
+    });
+    container.extend_from_slice(iv);
+    container.extend_from_slice(salt);
+    container.extend_from_slice(&iterations.to_le_bytes());
+    if wide {
+        container.extend_from_slice(&original_length.to_le_bytes());
+    } else {
+        container.push(original_length as u8);
+    }
+    container.extend_from_slice(payload);
+    container
+}
+
+/// 解析密文容器，iv_length由调用方根据算法指定，
+/// 校验版本号，拒绝识别不了的未来版本
+pub(crate) fn decode_container(blob: &[u8], iv_length: usize) -> Result<DecodedContainer> {
+    let mut taker = MemoryTaker::new(blob);
+
+    let mut version = [0u8; 1];
+    taker.take(&mut version)?;
+    if version[0] != CONTAINER_VERSION {
+        return Err(CrypterError::UnsupportedContainerVersion.into());
+    }
+
+    let mut algo_byte = [0u8; 1];
+    taker.take(&mut algo_byte)?;
+    let wide = algo_byte[0] & LENGTH_IS_WIDE_FLAG != 0;
+    let algorithm_id = algo_byte[0] & !LENGTH_IS_WIDE_FLAG;
+
+    let mut iv = vec![0u8; iv_length];
+    taker.take(&mut iv)?;
+
+    let mut salt = vec![0u8; SALT_LENGTH];
+    taker.take(&mut salt)?;
+
+    let mut iterations_bytes = [0u8; 4];
+    taker.take(&mut iterations_bytes)?;
+    let iterations = u32::from_le_bytes(iterations_bytes);
+
+    let original_length = if wide {
+        let mut length_bytes = [0u8; 8];
+        taker.take(&mut length_bytes)?;
+        u64::from_le_bytes(length_bytes)
+    } else {
+        let mut length_byte = [0u8; 1];
+        taker.take(&mut length_byte)?;
+        length_byte[0] as u64
+    };
+
+    let payload = taker.take_all()?;
+
+    Ok(DecodedContainer {
+        algorithm_id,
+        iv,
+        salt,
+        iterations,
+        original_length,
+        payload,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_roundtrip_short() {
+        let iv = [0x01u8; 12];
+        let salt = [0x03u8; SALT_LENGTH];
+        let payload = b"ciphertext".to_vec();
+        let container = encode_container(2, &iv, &salt, 1000, 5, &payload);
+        let decoded = decode_container(&container, iv.len()).unwrap();
+        assert_eq!(decoded.algorithm_id, 2);
+        assert_eq!(decoded.iv, iv.to_vec());
+        assert_eq!(decoded.salt, salt.to_vec());
+        assert_eq!(decoded.iterations, 1000);
+        assert_eq!(decoded.original_length, 5);
+        assert_eq!(decoded.payload, payload);
+    }
+
+    #[test]
+    fn test_container_roundtrip_wide_length() {
+        let iv = [0x02u8; 16];
+        let salt = [0x04u8; SALT_LENGTH];
+        let payload = vec![0xAAu8; 64];
+        let container = encode_container(1, &iv, &salt, 100_000, 4096, &payload);
+        let decoded = decode_container(&container, iv.len()).unwrap();
+        assert_eq!(decoded.algorithm_id, 1);
+        assert_eq!(decoded.salt, salt.to_vec());
+        assert_eq!(decoded.iterations, 100_000);
+        assert_eq!(decoded.original_length, 4096);
+        assert_eq!(decoded.payload, payload);
+    }
+
+    #[test]
+    fn test_container_rejects_unknown_version() {
+        let mut container = encode_container(1, &[0u8; 12], &[0u8; SALT_LENGTH], 1000, 5, b"abc");
+        container[0] = CONTAINER_VERSION + 1;
+        assert!(decode_container(&container, 12).is_err());
+    }
+}
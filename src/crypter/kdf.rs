@@ -0,0 +1,71 @@
+use crate::hash::hmac_sha256;
+use crate::*;
+
+/// 随机盐的长度
+pub const SALT_LENGTH: usize = 16;
+/// 未显式指定时使用的默认迭代次数
+pub const DEFAULT_KDF_ITERATIONS: u32 = 100_000;
+
+/// PBKDF2-HMAC-SHA256：把password和salt拉伸成key_length字节的密钥
+///
+/// 每个32字节的输出块计算为`T_i = U_1 ^ U_2 ^ ... ^ U_c`，其中
+/// `U_1 = HMAC(password, salt || be32(i))`，`U_j = HMAC(password, U_{j-1})`，
+/// 按块拼接直到达到key_length后截断
+pub fn derive_key(password: &[u8], salt: &[u8], iterations: u32, key_length: usize) -> Result<ByteVector> {
+    if password.is_empty() {
+        return Err(CrypterError::EmptyPasswordNotAllowed.into());
+    }
+
+    let mut derived = ByteVector::new();
+    let mut block_index: u32 = 1;
+    while derived.len() < key_length {
+        derived.extend_from_slice(&derive_block(password, salt, iterations, block_index)?);
+        block_index += 1;
+    }
+    derived.truncate(key_length);
+    Ok(derived)
+}
+
+/// 计算PBKDF2的第block_index个输出块
+fn derive_block(password: &[u8], salt: &[u8], iterations: u32, block_index: u32) -> Result<ByteVector> {
+    let mut salted = salt.to_vec();
+    salted.extend_from_slice(&block_index.to_be_bytes());
+
+    let mut u = hmac_sha256(password, &salted);
+    let mut t = u.clone();
+    for _ in 1..iterations.max(1) {
+        u = hmac_sha256(password, &u);
+        t = xor(&t, &u)?;
+    }
+    Ok(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_key_length() {
+        let key = derive_key(b"qwerty", b"0123456789abcdef", 1000, 32).unwrap();
+        assert_eq!(key.len(), 32);
+    }
+
+    #[test]
+    fn test_derive_key_deterministic() {
+        let key1 = derive_key(b"qwerty", b"0123456789abcdef", 1000, 16).unwrap();
+        let key2 = derive_key(b"qwerty", b"0123456789abcdef", 1000, 16).unwrap();
+        assert_eq!(key1, key2);
+    }
+
+    #[test]
+    fn test_derive_key_differs_by_salt() {
+        let key1 = derive_key(b"qwerty", b"0123456789abcdef", 1000, 16).unwrap();
+        let key2 = derive_key(b"qwerty", b"fedcba9876543210", 1000, 16).unwrap();
+        assert_ne!(key1, key2);
+    }
+
+    #[test]
+    fn test_derive_key_rejects_empty_password() {
+        assert!(derive_key(b"", b"0123456789abcdef", 1000, 16).is_err());
+    }
+}
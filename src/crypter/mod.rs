@@ -0,0 +1,193 @@
+use std::marker::PhantomData;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::encoding::hex::HexEncoding;
+use crate::encoding::EncodingTrait;
+use crate::*;
+
+pub mod aead;
+pub mod blockmode;
+pub mod chacha20;
+pub mod container;
+pub mod kdf;
+pub mod sm4;
+pub mod stream;
+
+pub use blockmode::{BlockCipherTrait, BlockMode, Cbc, Ctr};
+pub use stream::{DecryptReader, EncryptWriter};
+
+/// 非分组密码算法在流式读写时使用的缓冲分块大小
+pub(crate) const STREAM_CHUNK_LENGTH: usize = 4096;
+
+/// 流式读写时一个分块的长度：分组密码使用自身的分组长度，
+/// 流密码没有分组长度约束，使用固定的缓冲分块大小
+pub(crate) fn chunk_length(t: CipherAlgorithmType) -> usize {
+    match t {
+        CipherAlgorithmType::Stream => STREAM_CHUNK_LENGTH,
+        CipherAlgorithmType::Block(block_length) => block_length,
+    }
+}
+
+use container::{decode_container, encode_container};
+
+pub use aead::AeadTrait;
+
+/// 密码算法的分组类型，流密码没有分组长度，分组密码需要记录分组长度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherAlgorithmType {
+    /// 流密码
+    Stream,
+    /// 分组密码，携带分组长度
+    Block(usize),
+}
+
+/// 密码算法的基础属性
+pub trait CipherAlgorithmBaseTrait {
+    /// 该算法要求的IV长度
+    const IV_LENGTH: usize;
+    /// 该算法要求的Key长度
+    const KEY_LENGTH: usize;
+    /// 该算法的类型
+    const CIPHER_ALGORITHM_TYPE: CipherAlgorithmType;
+    /// 该算法在密文容器中的标识字节，取值范围为0..=0x7F
+    /// （最高位被容器格式用于标记原始长度字段的宽度）
+    const ALGORITHM_ID: u8;
+}
+
+/// 密码算法的加解密操作
+///
+/// 加密和解密拆分为两个方法：流密码的加解密本质上是同一段异或逻辑，
+/// 可以共用实现；分组密码（例如使用CBC模式的SM4）加解密并不对称，
+/// 需要分别实现
+pub trait CipherAlgorithmTrait: CipherAlgorithmBaseTrait {
+    /// 对src_data进行加密，结果写入dst_data
+    ///
+    /// 调用者需要自行保证多次调用之间数据是连续的
+    fn encrypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()>;
+    /// 对src_data进行解密，结果写入dst_data
+    ///
+    /// 调用者需要自行保证多次调用之间数据是连续的
+    fn decrypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()>;
+}
+
+/// 通过IV和Key构造密码算法实例
+pub trait IVKeyNewTrait {
+    /// 使用IV和Key构造一个新的算法实例
+    fn new(iv: &[u8], key: &[u8]) -> Result<Self>
+    where
+        Self: Sized;
+}
+
+/// 将plaintext补0到block_length的整数倍，真实长度由密文容器记录，
+/// 解密时按容器里的长度截断即可，不需要猜测填充边界
+pub(crate) fn pad_to_block_length(plaintext: &[u8], block_length: usize) -> ByteVector {
+    let padded_length = (plaintext.len() + block_length - 1) / block_length.max(1) * block_length;
+    let mut padded = vec![0u8; padded_length.max(block_length)];
+    padded[..plaintext.len()].copy_from_slice(plaintext);
+    padded
+}
+
+/// 针对字符串的加解密封装
+pub trait StringCrypterTrait {
+    /// 使用password对plaintext进行加密，返回编码后的字符串密文
+    fn encrypt(&self, plaintext: &str, password: &str) -> Result<String>;
+    /// 使用password对encode后的字符串密文进行解密，返回原始字符串
+    fn decrypt(&self, ciphertext: &str, password: &str) -> Result<String>;
+}
+
+/// 字符串加解密器，内部随机生成IV，并将IV和密文一起编码输出
+pub struct StringCrypter<A: CipherAlgorithmTrait + IVKeyNewTrait> {
+    m_iterations: u32,
+    _marker: PhantomData<A>,
+}
+
+impl<A: CipherAlgorithmTrait + IVKeyNewTrait> Default for StringCrypter<A> {
+    /// 使用默认的KDF迭代次数构造
+    fn default() -> Self {
+        StringCrypter {
+            m_iterations: kdf::DEFAULT_KDF_ITERATIONS,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: CipherAlgorithmTrait + IVKeyNewTrait> StringCrypter<A> {
+    /// 使用调用方指定的KDF迭代次数构造，用于按部署场景调整安全性与性能的取舍
+    pub fn with_iterations(iterations: u32) -> Self {
+        StringCrypter {
+            m_iterations: iterations,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: CipherAlgorithmTrait + IVKeyNewTrait> StringCrypterTrait for StringCrypter<A> {
+    fn encrypt(&self, plaintext: &str, password: &str) -> Result<String> {
+        if plaintext.is_empty() {
+            return Err(CrypterError::EmptyStringNotAllowed.into());
+        }
+
+        let mut salt = vec![0u8; kdf::SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let iterations = self.m_iterations;
+        let key = kdf::derive_key(password.as_bytes(), &salt, iterations, A::KEY_LENGTH)?;
+
+        let mut iv = vec![0u8; A::IV_LENGTH];
+        OsRng.fill_bytes(&mut iv);
+
+        let src = match A::CIPHER_ALGORITHM_TYPE {
+            CipherAlgorithmType::Stream => plaintext.as_bytes().to_vec(),
+            CipherAlgorithmType::Block(block_length) => {
+                pad_to_block_length(plaintext.as_bytes(), block_length)
+            }
+        };
+
+        let mut algo = A::new(&iv, &key)?;
+        let mut dst = vec![0u8; src.len()];
+        algo.encrypt(&src, &mut dst)?;
+
+        let container = encode_container(
+            A::ALGORITHM_ID,
+            &iv,
+            &salt,
+            iterations,
+            plaintext.len() as u64,
+            &dst,
+        );
+        Ok(HexEncoding.encode(&container))
+    }
+
+    fn decrypt(&self, ciphertext: &str, password: &str) -> Result<String> {
+        if ciphertext.is_empty() {
+            return Err(CrypterError::EmptyStringNotAllowed.into());
+        }
+
+        let blob = HexEncoding
+            .decode(ciphertext)
+            .map_err(|_| CrypterError::StringDecodingFailed)?;
+        let container = decode_container(&blob, A::IV_LENGTH)?;
+        if container.algorithm_id != A::ALGORITHM_ID {
+            return Err(CrypterError::UnknownAlgorithmId.into());
+        }
+
+        let key = kdf::derive_key(
+            password.as_bytes(),
+            &container.salt,
+            container.iterations,
+            A::KEY_LENGTH,
+        )?;
+        let mut algo = A::new(&container.iv, &key)?;
+        let mut dst = vec![0u8; container.payload.len()];
+        algo.decrypt(&container.payload, &mut dst)?;
+
+        let original_length = container.original_length as usize;
+        if original_length > dst.len() {
+            return Err(CrypterError::StringDecodingFailed.into());
+        }
+        dst.truncate(original_length);
+
+        String::from_utf8(dst).map_err(|_| CrypterError::StringDecodingFailed.into())
+    }
+}
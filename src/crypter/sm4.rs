@@ -1,7 +1,8 @@
 use gm_sm4::Sm4Cipher;
 
 use crate::crypter::{
-    CipherAlgorithmBaseTrait, CipherAlgorithmTrait, CipherAlgorithmType, IVKeyNewTrait,
+    BlockCipherTrait, BlockMode, Cbc, CipherAlgorithmBaseTrait, CipherAlgorithmTrait,
+    CipherAlgorithmType, IVKeyNewTrait,
 };
 
 use crate::*;
@@ -13,58 +14,73 @@ pub const SM4_KEY_LENGTH: usize = 16;
 /// SM4的分组长度
 pub const SM4_BLOCK_LENGTH: usize = 16;
 
-/// SM4密码算法
-pub struct Sm4CipherAlgorithm {
-    m_cipher: Sm4Cipher,
-    m_iv: [u8; SM4_IV_LENGTH],
-    m_prev_block: [u8; SM4_IV_LENGTH],
+impl BlockCipherTrait for Sm4Cipher {
+    const BLOCK_LENGTH: usize = SM4_BLOCK_LENGTH;
+
+    fn encrypt_block(&self, block: &[u8]) -> Result<ByteVector> {
+        let encrypted = self.encrypt(block).map_err(|_| CrypterError::CryptionFailed)?;
+        Ok(encrypted.to_vec())
+    }
+
+    fn decrypt_block(&self, block: &[u8]) -> Result<ByteVector> {
+        let decrypted = self.decrypt(block).map_err(|_| CrypterError::CryptionFailed)?;
+        Ok(decrypted.to_vec())
+    }
 }
 
-impl CipherAlgorithmBaseTrait for Sm4CipherAlgorithm {
-    const IV_LENGTH: usize = SM4_IV_LENGTH;
-    const KEY_LENGTH: usize = SM4_KEY_LENGTH;
-    const CIPHER_ALGORITHM_TYPE: CipherAlgorithmType = CipherAlgorithmType::Block(SM4_BLOCK_LENGTH);
+/// SM4密码算法，工作模式由类型参数M决定（默认CBC），
+/// 只负责分组长度的切分与模式的衔接，具体的单分组加解密交给BlockMode
+pub struct Sm4CipherAlgorithm<M: BlockMode<Sm4Cipher> + Default = Cbc> {
+    m_cipher: Sm4Cipher,
+    /// 对CBC是上一个密文分组，对CTR是正在运行的计数器，由BlockMode就地推进
+    m_prev_block: [u8; SM4_IV_LENGTH],
+    m_mode: M,
 }
 
-impl CipherAlgorithmTrait for Sm4CipherAlgorithm {
-    fn crypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()> {
+impl<M: BlockMode<Sm4Cipher> + Default> Sm4CipherAlgorithm<M> {
+    fn crypt_blocks(&mut self, src_data: &[u8], dst_data: &mut [u8], encrypting: bool) -> Result<()> {
         if src_data.len() != dst_data.len() {
             return Err(CrypterError::CryptionFailed.into());
         }
-        
         if src_data.len() % SM4_BLOCK_LENGTH != 0 {
             return Err(CrypterError::CryptionFailed.into());
         }
 
-        // 使用保存的prev_block状态，而不是每次都重置为IV
-        let mut prev_block = self.m_prev_block;
-        
         for (src_chunk, dst_chunk) in src_data
             .chunks_exact(SM4_BLOCK_LENGTH)
             .zip(dst_data.chunks_exact_mut(SM4_BLOCK_LENGTH))
         {
-            // 先与前一个密文块异或
-            let mut block = [0u8; SM4_BLOCK_LENGTH];
-            for i in 0..SM4_BLOCK_LENGTH {
-                block[i] = src_chunk[i] ^ prev_block[i];
+            if encrypting {
+                self.m_mode
+                    .encrypt_block(&self.m_cipher, &mut self.m_prev_block, dst_chunk, src_chunk)?;
+            } else {
+                self.m_mode
+                    .decrypt_block(&self.m_cipher, &mut self.m_prev_block, dst_chunk, src_chunk)?;
             }
-            
-            // SM4加密
-            let encrypted = self.m_cipher.encrypt(&block)
-                .map_err(|_| CrypterError::CryptionFailed)?;
-            
-            dst_chunk.copy_from_slice(&encrypted);
-            prev_block.copy_from_slice(&encrypted);
         }
-        
-        // 保存最后一个密文块作为下次调用的prev_block
-        self.m_prev_block = prev_block;
-        
+
         Ok(())
     }
 }
 
-impl IVKeyNewTrait for Sm4CipherAlgorithm {
+impl<M: BlockMode<Sm4Cipher> + Default> CipherAlgorithmBaseTrait for Sm4CipherAlgorithm<M> {
+    const IV_LENGTH: usize = SM4_IV_LENGTH;
+    const KEY_LENGTH: usize = SM4_KEY_LENGTH;
+    const CIPHER_ALGORITHM_TYPE: CipherAlgorithmType = CipherAlgorithmType::Block(SM4_BLOCK_LENGTH);
+    const ALGORITHM_ID: u8 = 1;
+}
+
+impl<M: BlockMode<Sm4Cipher> + Default> CipherAlgorithmTrait for Sm4CipherAlgorithm<M> {
+    fn encrypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()> {
+        self.crypt_blocks(src_data, dst_data, true)
+    }
+
+    fn decrypt(&mut self, src_data: &[u8], dst_data: &mut [u8]) -> Result<()> {
+        self.crypt_blocks(src_data, dst_data, false)
+    }
+}
+
+impl<M: BlockMode<Sm4Cipher> + Default> IVKeyNewTrait for Sm4CipherAlgorithm<M> {
     fn new(iv: &[u8], key: &[u8]) -> Result<Self>
     where
         Self: Sized,
@@ -75,18 +91,16 @@ impl IVKeyNewTrait for Sm4CipherAlgorithm {
         if key.len() != Self::KEY_LENGTH {
             return Err(CrypterError::InvalidKeyLength.into());
         }
-        
-        let cipher = Sm4Cipher::new(key)
-            .map_err(|_| CrypterError::CryptionFailed)?;
-        
-        let mut iv_array = [0u8; SM4_IV_LENGTH];
-        iv_array.copy_from_slice(iv);
-        
+
+        let cipher = Sm4Cipher::new(key).map_err(|_| CrypterError::CryptionFailed)?;
+
+        let mut prev_block = [0u8; SM4_IV_LENGTH];
+        prev_block.copy_from_slice(iv);
+
         Ok(Sm4CipherAlgorithm {
             m_cipher: cipher,
-            m_iv: iv_array,
-            // 初始化时，prev_block设置为IV
-            m_prev_block: iv_array,
+            m_prev_block: prev_block,
+            m_mode: M::default(),
         })
     }
 }
@@ -94,11 +108,12 @@ impl IVKeyNewTrait for Sm4CipherAlgorithm {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::crypter::{StringCrypter, StringCrypterTrait};
+    use crate::crypter::{Ctr, StringCrypter, StringCrypterTrait};
 
     #[test]
     fn test_sm4() {
-        let string_crypter = StringCrypter::<Sm4CipherAlgorithm>::default();
+        // 测试里用很小的迭代次数，避免每次跑测试都要等真实的PBKDF2开销
+        let string_crypter = StringCrypter::<Sm4CipherAlgorithm>::with_iterations(4);
         let ciphertext = string_crypter.encrypt("1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZ", "qwerty").unwrap();
         println!("ciphertext: {ciphertext}");
         let plaintext = string_crypter.decrypt(&ciphertext, "qwerty").unwrap();
@@ -108,36 +123,61 @@ mod tests {
 
     #[test]
     fn test_sm4_long_string() {
-        let string_crypter = StringCrypter::<Sm4CipherAlgorithm>::default();
-        
+        let string_crypter = StringCrypter::<Sm4CipherAlgorithm>::with_iterations(4);
+
         // 优化后的字符串生成过程
         let pattern = "1234567890abcdefghij";
         let target_length = 10_000;
-        
+
         // 计算需要重复的完整次数和剩余字符数
         let full_repeats = target_length / pattern.len();
         let remainder = target_length % pattern.len();
-        
+
         // 使用repeat方法生成完整重复部分，然后添加剩余部分
         let test_string = if remainder == 0 {
             pattern.repeat(full_repeats)
         } else {
             format!("{}{}", pattern.repeat(full_repeats), &pattern[..remainder])
         };
-        
+
         println!("测试字符串长度: {}", test_string.len());
-        
+
         // 加密
         let ciphertext = string_crypter.encrypt(&test_string, "qwerty").unwrap();
         println!("密文长度: {}", ciphertext.len());
         println!("密文: {}", &ciphertext[..]);
-        
+
         // 解密
         let plaintext = string_crypter.decrypt(&ciphertext, "qwerty").unwrap();
         println!("解密后字符串长度: {}", plaintext.len());
-        
+
         // 验证加密解密后是否相等
         assert_eq!(plaintext, test_string);
         println!("SM4长字符串加密解密测试通过！");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sm4_ctr_roundtrip() {
+        let string_crypter = StringCrypter::<Sm4CipherAlgorithm<Ctr>>::with_iterations(4);
+        let ciphertext = string_crypter.encrypt("1234567890ABCDEF", "qwerty").unwrap();
+        let plaintext = string_crypter.decrypt(&ciphertext, "qwerty").unwrap();
+        assert_eq!(plaintext, "1234567890ABCDEF");
+    }
+
+    #[test]
+    fn test_sm4_cbc_decrypt_is_inverse_of_encrypt() {
+        let key = [0x01u8; SM4_KEY_LENGTH];
+        let iv = [0x02u8; SM4_IV_LENGTH];
+        let plaintext = [0xABu8; SM4_BLOCK_LENGTH * 3];
+
+        let mut encryptor = Sm4CipherAlgorithm::<Cbc>::new(&iv, &key).unwrap();
+        let mut ciphertext = vec![0u8; plaintext.len()];
+        encryptor.encrypt(&plaintext, &mut ciphertext).unwrap();
+
+        let mut decryptor = Sm4CipherAlgorithm::<Cbc>::new(&iv, &key).unwrap();
+        let mut decrypted = vec![0u8; ciphertext.len()];
+        decryptor.decrypt(&ciphertext, &mut decrypted).unwrap();
+
+        assert_eq!(decrypted, plaintext.to_vec());
+    }
+}
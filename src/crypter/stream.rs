@@ -0,0 +1,319 @@
+use std::io::{self, Read, Write};
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::crypter::container::CONTAINER_VERSION;
+use crate::crypter::{
+    chunk_length, kdf, pad_to_block_length, CipherAlgorithmTrait, CipherAlgorithmType,
+    IVKeyNewTrait, STREAM_CHUNK_LENGTH,
+};
+use crate::*;
+
+/// 流式加密末尾记录的原始长度字段宽度，固定为8字节，
+/// 因为写入时还不知道总长度，无法像一次性容器那样按长度选择宽度
+const TRAILER_LENGTH: usize = 8;
+
+fn io_err(e: Error) -> io::Error {
+    io::Error::other(e)
+}
+
+/// 对`Write`进行包装的流式加密器
+///
+/// 构造时立即写出容器头（版本号、算法标识、IV、KDF盐、KDF迭代次数），
+/// 之后每次write都把数据缓冲到分块大小，攒够一整块就加密写出，
+/// 调用finish()时加密并写出最后一个（可能不完整、需要补0的）分块，
+/// 再追加8字节小端原始长度，使解密方可以在读到末尾后去掉补齐的0字节
+pub struct EncryptWriter<W: Write, A: CipherAlgorithmTrait + IVKeyNewTrait> {
+    m_inner: W,
+    m_algo: A,
+    m_chunk_length: usize,
+    m_buffer: ByteVector,
+    m_total_length: u64,
+}
+
+impl<W: Write, A: CipherAlgorithmTrait + IVKeyNewTrait> EncryptWriter<W, A> {
+    /// 创建一个新的加密写入器，使用默认的KDF迭代次数，随机生成IV和KDF盐并立即写出容器头
+    pub fn new(inner: W, password: &str) -> Result<Self> {
+        Self::with_iterations(inner, password, kdf::DEFAULT_KDF_ITERATIONS)
+    }
+
+    /// 创建一个新的加密写入器，使用调用方指定的KDF迭代次数，
+    /// 随机生成IV和KDF盐并立即写出容器头
+    pub fn with_iterations(mut inner: W, password: &str, iterations: u32) -> Result<Self> {
+        let mut salt = vec![0u8; kdf::SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let key = kdf::derive_key(password.as_bytes(), &salt, iterations, A::KEY_LENGTH)?;
+
+        let mut iv = vec![0u8; A::IV_LENGTH];
+        OsRng.fill_bytes(&mut iv);
+        let algo = A::new(&iv, &key)?;
+
+        let mut header = ByteVector::new();
+        header.push(CONTAINER_VERSION);
+        header.push(A::ALGORITHM_ID);
+        header.extend_from_slice(&iv);
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&iterations.to_le_bytes());
+        inner
+            .write_all(&header)
+            .map_err(|_| CrypterError::BufferFlushFailed)?;
+
+        Ok(EncryptWriter {
+            m_inner: inner,
+            m_algo: algo,
+            m_chunk_length: chunk_length(A::CIPHER_ALGORITHM_TYPE),
+            m_buffer: ByteVector::new(),
+            m_total_length: 0,
+        })
+    }
+
+    fn encrypt_and_write(&mut self, plain: &[u8]) -> Result<()> {
+        let mut cipher = vec![0u8; plain.len()];
+        self.m_algo.encrypt(plain, &mut cipher)?;
+        self.m_inner
+            .write_all(&cipher)
+            .map_err(|_| CrypterError::BufferFlushFailed)?;
+        Ok(())
+    }
+
+    /// 结束加密：写出最后一个分块和原始长度，返回底层的W
+    pub fn finish(mut self) -> Result<W> {
+        if !self.m_buffer.is_empty() {
+            let last_block = match A::CIPHER_ALGORITHM_TYPE {
+                CipherAlgorithmType::Stream => std::mem::take(&mut self.m_buffer),
+                CipherAlgorithmType::Block(block_length) => {
+                    pad_to_block_length(&self.m_buffer, block_length)
+                }
+            };
+            self.encrypt_and_write(&last_block)?;
+            self.m_buffer.clear();
+        }
+
+        self.m_inner
+            .write_all(&self.m_total_length.to_le_bytes())
+            .map_err(|_| CrypterError::BufferFlushFailed)?;
+        self.m_inner
+            .flush()
+            .map_err(|_| CrypterError::BufferFlushFailed)?;
+        Ok(self.m_inner)
+    }
+}
+
+impl<W: Write, A: CipherAlgorithmTrait + IVKeyNewTrait> Write for EncryptWriter<W, A> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.m_buffer.extend_from_slice(buf);
+        self.m_total_length += buf.len() as u64;
+
+        while self.m_buffer.len() >= self.m_chunk_length {
+            let block: ByteVector = self.m_buffer.drain(0..self.m_chunk_length).collect();
+            self.encrypt_and_write(&block).map_err(io_err)?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.m_inner.flush()
+    }
+}
+
+/// 对`Read`进行包装的流式解密器
+///
+/// 构造时先读取容器头并校验版本号/算法标识，之后每次fill都从底层读取新数据，
+/// 始终保留至少一个分块加上8字节长度字段的"尾部"不解密，
+/// 直到确认读到了底层的末尾，才能确定这部分到底是密文还是长度字段，
+/// 进而用记录的原始长度去掉分组密码补齐的0字节
+pub struct DecryptReader<R: Read, A: CipherAlgorithmTrait + IVKeyNewTrait> {
+    m_inner: R,
+    m_algo: A,
+    m_chunk_length: usize,
+    m_cipher_buffer: ByteVector,
+    m_plain_buffer: ByteVector,
+    m_plain_buffer_pos: usize,
+    m_emitted: u64,
+    m_total_length: Option<u64>,
+    m_inner_eof: bool,
+}
+
+impl<R: Read, A: CipherAlgorithmTrait + IVKeyNewTrait> DecryptReader<R, A> {
+    /// 创建一个新的解密读取器，读取并校验容器头
+    pub fn new(mut inner: R, password: &str) -> Result<Self> {
+        let mut header = [0u8; 2];
+        inner
+            .read_exact(&mut header)
+            .map_err(|_| CrypterError::StringDecodingFailed)?;
+        if header[0] != CONTAINER_VERSION {
+            return Err(CrypterError::UnsupportedContainerVersion.into());
+        }
+        if header[1] != A::ALGORITHM_ID {
+            return Err(CrypterError::UnknownAlgorithmId.into());
+        }
+
+        let mut iv = vec![0u8; A::IV_LENGTH];
+        inner
+            .read_exact(&mut iv)
+            .map_err(|_| CrypterError::StringDecodingFailed)?;
+
+        let mut salt = vec![0u8; kdf::SALT_LENGTH];
+        inner
+            .read_exact(&mut salt)
+            .map_err(|_| CrypterError::StringDecodingFailed)?;
+
+        let mut iterations_bytes = [0u8; 4];
+        inner
+            .read_exact(&mut iterations_bytes)
+            .map_err(|_| CrypterError::StringDecodingFailed)?;
+        let iterations = u32::from_le_bytes(iterations_bytes);
+
+        let key = kdf::derive_key(password.as_bytes(), &salt, iterations, A::KEY_LENGTH)?;
+        let algo = A::new(&iv, &key)?;
+
+        Ok(DecryptReader {
+            m_inner: inner,
+            m_algo: algo,
+            m_chunk_length: chunk_length(A::CIPHER_ALGORITHM_TYPE),
+            m_cipher_buffer: ByteVector::new(),
+            m_plain_buffer: ByteVector::new(),
+            m_plain_buffer_pos: 0,
+            m_emitted: 0,
+            m_total_length: None,
+            m_inner_eof: false,
+        })
+    }
+
+    /// 从底层读取更多密文，直到确认还能安全处理至少一个分块，或者已经读到末尾
+    fn fill_cipher_buffer(&mut self) -> Result<()> {
+        let held_back = self.m_chunk_length + TRAILER_LENGTH;
+        let mut read_buf = vec![0u8; self.m_chunk_length.max(STREAM_CHUNK_LENGTH)];
+
+        while !self.m_inner_eof && self.m_cipher_buffer.len() < held_back + self.m_chunk_length {
+            let n = self
+                .m_inner
+                .read(&mut read_buf)
+                .map_err(|_| CrypterError::StringDecodingFailed)?;
+            if n == 0 {
+                self.m_inner_eof = true;
+            } else {
+                self.m_cipher_buffer.extend_from_slice(&read_buf[..n]);
+            }
+        }
+        Ok(())
+    }
+
+    /// 解密一个分块并追加到明文缓冲区
+    fn decrypt_chunk(&mut self, chunk: &[u8]) -> Result<()> {
+        let mut plain = vec![0u8; chunk.len()];
+        self.m_algo.decrypt(chunk, &mut plain)?;
+        self.m_plain_buffer.extend_from_slice(&plain);
+        Ok(())
+    }
+
+    /// 驱动状态机：尽量让m_plain_buffer中有可读的数据
+    ///
+    /// 已被读走的前缀会在这里被drain掉，保证m_plain_buffer只保存
+    /// 尚未交给调用者的数据，不会随着文件增大而无限增长
+    fn fill_plain_buffer(&mut self) -> Result<()> {
+        if self.m_plain_buffer_pos > 0 {
+            self.m_plain_buffer.drain(0..self.m_plain_buffer_pos);
+            self.m_plain_buffer_pos = 0;
+        }
+
+        while self.m_plain_buffer.is_empty() && self.m_total_length.is_none() {
+            self.fill_cipher_buffer()?;
+
+            let held_back = self.m_chunk_length + TRAILER_LENGTH;
+            if !self.m_inner_eof {
+                // 还没读到末尾，前面超出held_back部分的整块可以安全解密
+                while self.m_cipher_buffer.len() > held_back
+                    && self.m_cipher_buffer.len() - held_back >= self.m_chunk_length
+                {
+                    let chunk: ByteVector =
+                        self.m_cipher_buffer.drain(0..self.m_chunk_length).collect();
+                    self.decrypt_chunk(&chunk)?;
+                }
+            } else {
+                // 已经读到末尾，剩余的内容是"若干完整分块 + 尾部长度字段"
+                if self.m_cipher_buffer.len() < TRAILER_LENGTH {
+                    return Err(CrypterError::StringDecodingFailed.into());
+                }
+                let split_at = self.m_cipher_buffer.len() - TRAILER_LENGTH;
+                let trailer: Vec<u8> = self.m_cipher_buffer.split_off(split_at);
+                let mut trailer_bytes = [0u8; TRAILER_LENGTH];
+                trailer_bytes.copy_from_slice(&trailer);
+                let total_length = u64::from_le_bytes(trailer_bytes);
+
+                let remainder = std::mem::take(&mut self.m_cipher_buffer);
+                if !remainder.is_empty() {
+                    self.decrypt_chunk(&remainder)?;
+                }
+
+                // 分组密码在最后一块里补的0字节需要根据记录的原始长度去掉，
+                // 这部分多出来的内容只会出现在刚解密出的尾部
+                let produced = self.m_emitted + self.m_plain_buffer.len() as u64;
+                if total_length < produced {
+                    let excess = (produced - total_length) as usize;
+                    let new_len = self.m_plain_buffer.len() - excess;
+                    self.m_plain_buffer.truncate(new_len);
+                }
+                self.m_total_length = Some(total_length);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read, A: CipherAlgorithmTrait + IVKeyNewTrait> Read for DecryptReader<R, A> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.fill_plain_buffer().map_err(io_err)?;
+
+        let available = &self.m_plain_buffer[self.m_plain_buffer_pos..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.m_plain_buffer_pos += n;
+        self.m_emitted += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::crypter::chacha20::ChaCha20CipherAlgorithm;
+    use crate::crypter::sm4::Sm4CipherAlgorithm;
+
+    fn roundtrip<A: CipherAlgorithmTrait + IVKeyNewTrait>(plaintext: &[u8]) {
+        // 测试里用很小的迭代次数，避免每次跑测试都要等真实的PBKDF2开销
+        let mut writer = EncryptWriter::<_, A>::with_iterations(Vec::new(), "qwerty", 4).unwrap();
+        // 故意拆成很小的write调用，检验跨调用的缓冲衔接是否正确
+        for chunk in plaintext.chunks(3) {
+            writer.write_all(chunk).unwrap();
+        }
+        let ciphertext = writer.finish().unwrap();
+
+        let mut reader = DecryptReader::<_, A>::new(Cursor::new(ciphertext), "qwerty").unwrap();
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).unwrap();
+
+        assert_eq!(decrypted, plaintext.to_vec());
+    }
+
+    #[test]
+    fn test_stream_sm4_small() {
+        roundtrip::<Sm4CipherAlgorithm>(b"hello, stream!");
+    }
+
+    #[test]
+    fn test_stream_sm4_multi_block() {
+        let plaintext = vec![0x5Au8; 10_000];
+        roundtrip::<Sm4CipherAlgorithm>(&plaintext);
+    }
+
+    #[test]
+    fn test_stream_chacha20_roundtrip() {
+        let plaintext = vec![0xA5u8; 10_000];
+        roundtrip::<ChaCha20CipherAlgorithm>(&plaintext);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::encoding::EncodingTrait;
+use crate::*;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PAD: u8 = b'=';
+
+/// Base64编码器
+pub struct Base64Encoding;
+
+impl EncodingTrait for Base64Encoding {
+    fn encode(&self, data: &[u8]) -> String {
+        let mut s = String::with_capacity(data.len().div_ceil(3) * 4);
+        for chunk in data.chunks(3) {
+            let b0 = chunk[0];
+            let b1 = *chunk.get(1).unwrap_or(&0);
+            let b2 = *chunk.get(2).unwrap_or(&0);
+
+            s.push(ALPHABET[(b0 >> 2) as usize] as char);
+            s.push(ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+            s.push(if chunk.len() > 1 {
+                ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+            } else {
+                PAD as char
+            });
+            s.push(if chunk.len() > 2 {
+                ALPHABET[(b2 & 0x3f) as usize] as char
+            } else {
+                PAD as char
+            });
+        }
+        s
+    }
+
+    fn decode(&self, data: &str) -> Result<ByteVector> {
+        let bytes = data.as_bytes();
+        if !bytes.len().is_multiple_of(4) {
+            return Err(EncodingError::InvalidBase64.into());
+        }
+
+        let mut res = Vec::with_capacity(bytes.len() / 4 * 3);
+        for chunk in bytes.chunks_exact(4) {
+            let pad_count = chunk.iter().filter(|&&b| b == PAD).count();
+            if pad_count > 2 || chunk[..4 - pad_count].contains(&PAD) {
+                return Err(EncodingError::InvalidBase64.into());
+            }
+
+            let mut sextets = [0u8; 4];
+            for (i, &b) in chunk.iter().enumerate() {
+                sextets[i] = if b == PAD {
+                    0
+                } else {
+                    decode_char(b).ok_or(EncodingError::InvalidBase64)?
+                };
+            }
+
+            res.push((sextets[0] << 2) | (sextets[1] >> 4));
+            if pad_count < 2 {
+                res.push((sextets[1] << 4) | (sextets[2] >> 2));
+            }
+            if pad_count < 1 {
+                res.push((sextets[2] << 6) | sextets[3]);
+            }
+        }
+        Ok(res)
+    }
+}
+
+fn decode_char(c: u8) -> Option<u8> {
+    ALPHABET.iter().position(|&a| a == c).map(|i| i as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_roundtrip() {
+        let data = b"hello, world!";
+        let encoded = Base64Encoding.encode(data);
+        assert_eq!(encoded, "aGVsbG8sIHdvcmxkIQ==");
+        let decoded = Base64Encoding.decode(&encoded).unwrap();
+        assert_eq!(decoded, data.to_vec());
+    }
+
+    #[test]
+    fn test_base64_invalid() {
+        assert!(Base64Encoding.decode("abc").is_err());
+        assert!(Base64Encoding.decode("a=bc").is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::encoding::EncodingTrait;
+use crate::*;
+
+/// 16进制编码器
+pub struct HexEncoding;
+
+impl EncodingTrait for HexEncoding {
+    fn encode(&self, data: &[u8]) -> String {
+        let mut s = String::with_capacity(data.len() * 2);
+        for byte in data {
+            s.push_str(&format!("{:02x}", byte));
+        }
+        s
+    }
+
+    fn decode(&self, data: &str) -> Result<ByteVector> {
+        if !data.len().is_multiple_of(2) {
+            return Err(EncodingError::InvalidHex.into());
+        }
+        let mut res = Vec::with_capacity(data.len() / 2);
+        let bytes = data.as_bytes();
+        for chunk in bytes.chunks_exact(2) {
+            let hi = (chunk[0] as char).to_digit(16).ok_or(EncodingError::InvalidHex)?;
+            let lo = (chunk[1] as char).to_digit(16).ok_or(EncodingError::InvalidHex)?;
+            res.push(((hi << 4) | lo) as u8);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let data = b"hello, world!";
+        let encoded = HexEncoding.encode(data);
+        assert_eq!(encoded, "68656c6c6f2c20776f726c6421");
+        let decoded = HexEncoding.decode(&encoded).unwrap();
+        assert_eq!(decoded, data.to_vec());
+    }
+
+    #[test]
+    fn test_hex_invalid() {
+        assert!(HexEncoding.decode("xy").is_err());
+        assert!(HexEncoding.decode("abc").is_err());
+    }
+}
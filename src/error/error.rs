@@ -55,6 +55,12 @@ pub enum CrypterError {
 
     #[error("密码不能为空")]
     EmptyPasswordNotAllowed,
+
+    #[error("不支持的密文容器版本")]
+    UnsupportedContainerVersion,
+
+    #[error("未知的算法标识")]
+    UnknownAlgorithmId,
 }
 
 #[derive(Error, Debug)]
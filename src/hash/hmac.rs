@@ -0,0 +1,40 @@
+use ::hmac::{Hmac, Mac};
+use ::sha2::Sha256;
+
+use crate::*;
+
+/// HMAC-SHA256输出摘要的长度
+pub const HMAC_SHA256_OUTPUT_LENGTH: usize = 32;
+
+/// 计算HMAC-SHA256，key可以是任意长度
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> ByteVector {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC的key长度没有限制");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_output_length() {
+        let mac = hmac_sha256(b"key", b"message");
+        assert_eq!(mac.len(), HMAC_SHA256_OUTPUT_LENGTH);
+    }
+
+    #[test]
+    fn test_hmac_sha256_deterministic() {
+        let mac1 = hmac_sha256(b"key", b"message");
+        let mac2 = hmac_sha256(b"key", b"message");
+        assert_eq!(mac1, mac2);
+    }
+
+    #[test]
+    fn test_hmac_sha256_differs_by_key() {
+        let mac1 = hmac_sha256(b"key1", b"message");
+        let mac2 = hmac_sha256(b"key2", b"message");
+        assert_ne!(mac1, mac2);
+    }
+}
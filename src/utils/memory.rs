@@ -21,6 +21,20 @@ impl<const N: usize> ByteArray<N> {
     pub fn new() -> ByteArray<N> {
         ByteArray::<N>([0; N])
     }
+
+    /// 以恒定时间比较两个ByteArray是否相等
+    ///
+    /// 与`PartialEq`不同，本方法不会在遇到第一个不相等字节时提前返回，
+    /// 适用于比较MAC标签、口令摘要等不能通过比较耗时泄露信息的场景
+    ///
+    /// # 参数
+    /// * `other` - 要比较的另一个ByteArray
+    ///
+    /// # 返回值
+    /// 如果两个数组的所有元素都相等则返回true，否则返回false
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ct_eq(&self.0, &other.0)
+    }
 }
 
 /// 为ByteArray实现Default trait，提供默认值构造
@@ -57,6 +71,10 @@ impl<const N: usize> DerefMut for ByteArray<N> {
 impl<const N: usize> PartialEq for ByteArray<N> {
     /// 比较两个ByteArray是否相等
     ///
+    /// 本实现是普通的快速比较，会在遇到第一个不相等字节时提前返回，
+    /// 不具备恒定时间特性；比较MAC标签、口令摘要等秘密数据时，
+    /// 应使用[`ByteArray::ct_eq`]而非`==`
+    ///
     /// # 参数
     /// * `other` - 要比较的另一个ByteArray
     ///
@@ -261,6 +279,29 @@ pub fn xor(src1: &[u8], src2: &[u8]) -> Result<ByteVector> {
     Ok(res)
 }
 
+/// 以恒定时间比较两个字节切片是否相等
+///
+/// 比较耗时不依赖于两个切片具体在哪个字节上出现差异，只依赖于切片长度，
+/// 用于避免MAC标签、口令摘要等秘密数据的逐字节比较被时序攻击利用；
+/// 长度不同时直接视为不相等
+///
+/// # 参数
+/// * `a` - 第一个字节切片
+/// * `b` - 第二个字节切片
+///
+/// # 返回值
+/// 两个切片长度相同且所有字节都相等时返回true，否则返回false
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for i in 0..a.len() {
+        diff |= a[i] ^ b[i];
+    }
+    diff == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +315,22 @@ mod tests {
         println!("Res: {}", res);
     }
 
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"hello", b"hello"));
+        assert!(!ct_eq(b"hello", b"hellp"));
+        assert!(!ct_eq(b"hello", b"hell"));
+    }
+
+    #[test]
+    fn test_byte_array_ct_eq() {
+        let a = ByteArray::<4>::from(&[0x01, 0x02, 0x03, 0x04]);
+        let b = ByteArray::<4>::from(&[0x01, 0x02, 0x03, 0x04]);
+        let c = ByteArray::<4>::from(&[0x01, 0x02, 0x03, 0x05]);
+        assert!(a.ct_eq(&b));
+        assert!(!a.ct_eq(&c));
+    }
+
     #[test]
     fn test_memory_taker() {
         let v = b"123456789".to_vec();
@@ -0,0 +1,3 @@
+pub mod memory;
+
+pub use memory::*;